@@ -0,0 +1,215 @@
+//! Data-aware k-d tree cell partitioning scheme
+
+use core::ops::{Index, IndexMut};
+use num::NumCast;
+use core::cmp::{Ordering, PartialOrd};
+use alloc::vec::Vec;
+use alloc::vec;
+use nalgebra::{Dimension, BaseFloat};
+use partition::Partition;
+
+
+/// Data-aware subdivision.
+///
+/// Where [`Subdivide`](trait.Subdivide.html) splits space by geometry alone,
+/// `SubdivideWith` inspects the elements a node contains and places its split
+/// plane to balance element counts — the spatial analogue of balancing
+/// partition sizes across storage nodes. This keeps trees shallow on
+/// clustered data instead of forcing the fixed 2^n octant split of `Ncube`.
+pub trait SubdivideWith: Sized {
+    /// The element type examined when choosing a split.
+    type Elem;
+
+    /// Subdivide this cell using its contained elements.
+    ///
+    /// On success returns the dispatching node — a copy of `self` with the
+    /// chosen split plane recorded, so that `dispatch` routes elements into
+    /// the children — together with those children. Returns `None` when no
+    /// split leaves elements on both sides: for an empty slice, for points
+    /// that coincide on every axis, and when on every axis with positive
+    /// spread the median coordinate equals that axis's minimum or maximum.
+    fn subdivide_with(&self, elems: &[Self::Elem]) -> Option<(Self, Vec<Self>)>;
+}
+
+
+/// A k-d tree cell: an axis-aligned box that splits along a single axis.
+///
+/// The cell carries its own axis-aligned bounding box (`lower`/`upper`) rather
+/// than a center and width, because a median split produces children of
+/// unequal extent. Once a split plane has been chosen for a node — via
+/// [`split_plane`](#method.split_plane) and recorded with
+/// [`with_split`](#method.with_split) — `dispatch` routes an element by
+/// comparing its coordinate on `split_axis` against `split_value`.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KdCell<P, S> {
+    lower: P,
+    upper: P,
+    split: Option<(usize, S)>,
+}
+
+impl<P, S> KdCell<P, S>
+    where P: Dimension + Index<usize, Output=S> + IndexMut<usize, Output=S> + Copy,
+          S: BaseFloat + PartialOrd + NumCast,
+{
+    /// Create a new cell from its lower and upper corner.
+    pub fn new(lower: P, upper: P) -> KdCell<P, S> {
+        let dimension = Dimension::dimension(None::<P>);
+        for i in 0..dimension {
+            assert!(lower[i] <= upper[i]);
+        }
+        KdCell { lower: lower, upper: upper, split: None }
+    }
+
+    /// The lower corner of the cell.
+    pub fn lower(&self) -> P { self.lower }
+
+    /// The upper corner of the cell.
+    pub fn upper(&self) -> P { self.upper }
+
+    /// Record the split plane this cell dispatches on.
+    pub fn with_split(mut self, axis: usize, value: S) -> KdCell<P, S> {
+        self.split = Some((axis, value));
+        self
+    }
+
+    /// Choose a balancing split plane for the given elements.
+    ///
+    /// Picks the axis of largest spread and returns its median coordinate,
+    /// found in O(n) expected time via `select_nth_unstable_by`. Axes on which
+    /// all points coincide are skipped, as are axes whose median equals their
+    /// minimum or maximum coordinate (the split would leave one side empty);
+    /// the next-widest axis is tried instead. If no axis remains (empty slice,
+    /// fully degenerate point set, or only such skewed axes) this returns
+    /// `None`.
+    pub fn split_plane(&self, elems: &[P]) -> Option<(usize, S)> {
+        if elems.is_empty() {
+            return None;
+        }
+        let dimension = Dimension::dimension(None::<P>);
+        // Per-axis spread, widest first, skipping degenerate axes.
+        let mut axes: Vec<(usize, S)> = (0..dimension)
+            .map(|k| {
+                let mut lo = elems[0][k];
+                let mut hi = elems[0][k];
+                for p in &elems[1..] {
+                    if p[k] < lo { lo = p[k]; }
+                    if p[k] > hi { hi = p[k]; }
+                }
+                (k, hi - lo)
+            })
+            .filter(|&(_, spread)| spread > BaseFloat::zero())
+            .collect();
+        axes.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        for (axis, _) in axes {
+            let mut coords: Vec<S> = elems.iter().map(|p| p[axis]).collect();
+            let mid = coords.len() / 2;
+            coords.select_nth_unstable_by(mid, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            let median = coords[mid];
+            // Guard against a median at either end of the axis: elements
+            // `<= median` dispatch to child 0, so both sides must be non-empty.
+            if elems.iter().any(|p| p[axis] < median) && elems.iter().any(|p| p[axis] > median) {
+                return Some((axis, median));
+            }
+        }
+        None
+    }
+}
+
+impl<P, S> SubdivideWith for KdCell<P, S>
+    where P: Dimension + Index<usize, Output=S> + IndexMut<usize, Output=S> + Copy,
+          S: BaseFloat + PartialOrd + NumCast,
+{
+    type Elem = P;
+
+    fn subdivide_with(&self, elems: &[P]) -> Option<(KdCell<P, S>, Vec<KdCell<P, S>>)> {
+        let (axis, value) = match self.split_plane(elems) {
+            Some(split) => split,
+            None => return None,
+        };
+        // Two children, the parent box clipped at the split plane.
+        let mut lower_upper = self.upper;
+        lower_upper[axis] = value;
+        let mut upper_lower = self.lower;
+        upper_lower[axis] = value;
+        let children = vec![
+            KdCell { lower: self.lower, upper: lower_upper, split: None },
+            KdCell { lower: upper_lower, upper: self.upper, split: None },
+        ];
+        // The dispatching node records the split so `dispatch` routes on it.
+        Some((self.with_split(axis, value), children))
+    }
+}
+
+impl<P, S> Partition<P> for KdCell<P, S>
+    where P: Dimension + Index<usize, Output=S> + IndexMut<usize, Output=S> + Copy,
+          S: BaseFloat + PartialOrd + NumCast,
+{
+    fn contains(&self, elem: &P) -> bool {
+        // Lower-exclusive, upper-inclusive, matching `Ncube`/`Northotope` so a
+        // point on the dataset's max corner is contained by the root cell.
+        (0..Dimension::dimension(None::<P>))
+            .all(|i| (self.lower[i] < elem[i]) && (elem[i] <= self.upper[i]))
+    }
+
+    fn dispatch(&self, elem: &P) -> usize {
+        match self.split {
+            Some((axis, value)) => if elem[axis] <= value { 0 } else { 1 },
+            None => 0,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use nalgebra::Point2;
+    use super::*;
+    use partition::Partition;
+
+    #[test]
+    fn empty_slice_does_not_subdivide() {
+        let cell: KdCell<Point2<f32>, f32> = KdCell::new(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0));
+        assert!(cell.split_plane(&[]).is_none());
+        assert!(cell.subdivide_with(&[]).is_none());
+    }
+
+    #[test]
+    fn coincident_points_do_not_subdivide() {
+        let cell: KdCell<Point2<f32>, f32> = KdCell::new(Point2::new(0.0, 0.0), Point2::new(10.0, 10.0));
+        let pts = [Point2::new(3.0, 3.0), Point2::new(3.0, 3.0), Point2::new(3.0, 3.0)];
+        assert!(cell.split_plane(&pts).is_none());
+        assert!(cell.subdivide_with(&pts).is_none());
+    }
+
+    #[test]
+    fn duplicates_on_widest_axis_fall_through() {
+        let cell: KdCell<Point2<f32>, f32> = KdCell::new(Point2::new(0.0, 0.0), Point2::new(10.0, 10.0));
+        // x has the largest spread but its median is degenerate (three 0s),
+        // so the split must fall through to y.
+        let pts = [
+            Point2::new(0.0, 0.0),
+            Point2::new(0.0, 1.0),
+            Point2::new(0.0, 2.0),
+            Point2::new(10.0, 3.0),
+        ];
+        assert_eq!(cell.split_plane(&pts), Some((1, 2.0)));
+    }
+
+    #[test]
+    fn every_element_dispatches_into_a_containing_child() {
+        let cell: KdCell<Point2<f32>, f32> = KdCell::new(Point2::new(0.0, 0.0), Point2::new(10.0, 10.0));
+        let pts = [
+            Point2::new(1.0, 1.0),
+            Point2::new(2.0, 5.0),
+            Point2::new(8.0, 2.0),
+            Point2::new(6.0, 9.0),
+        ];
+        let (node, children) = cell.subdivide_with(&pts).unwrap();
+        for p in &pts {
+            let idx = node.dispatch(p);
+            assert!(children[idx].contains(p),
+                    "element {:?} dispatched to child {} that does not contain it", p, idx);
+        }
+    }
+}
@@ -1,16 +1,24 @@
 //! N-cube or hypercube partitioning scheme
 
-use std::ops::{Index, IndexMut};
-use num::{PrimInt, NumCast, Zero};
-use std::cmp::PartialOrd;
+use core::ops::{Index, IndexMut};
+use num::{NumCast, Zero};
+use core::cmp::PartialOrd;
+use alloc::vec::Vec;
 use nalgebra::{Dimension, BaseFloat, zero};
-#[cfg(any(test, feature = "arbitrary"))]
+#[cfg(all(feature = "std", any(test, feature = "arbitrary")))]
 use quickcheck::{Arbitrary, Gen};
 use partition::{Partition, Subdivide};
+use partition::northotope::Northotope;
 
 
 /// An N-cube based partitioning scheme
+///
+/// This is a thin, equal-extent wrapper around
+/// [`Northotope`](struct.Northotope.html): a single scalar `width` gives a
+/// half-extent of `width / 2` shared by every axis. The `Subdivide`/`Partition` logic
+/// is delegated to an orthotope built on demand.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ncube<P, S> {
     center: P,
     width: S,
@@ -24,6 +32,21 @@ impl<P, S: PartialOrd + Zero> Ncube<P, S> {
     }
 }
 
+impl<P, S> Ncube<P, S>
+    where P: Dimension + Index<usize, Output=S> + IndexMut<usize, Output=S> + Copy,
+          S: BaseFloat + PartialOrd + NumCast,
+{
+    /// The equal-extent orthotope this N-cube is equivalent to
+    fn orthotope(&self) -> Northotope<P, S> {
+        let _2: S = NumCast::from(2.0f64).unwrap();
+        let mut half_extents = self.center;
+        for i in 0..Dimension::dimension(None::<P>) {
+            half_extents[i] = self.width / _2;
+        }
+        Northotope::new(self.center, half_extents)
+    }
+}
+
 impl<P, S: Clone> Ncube<P, S> {
     /// The width of the N-cube
     pub fn width(&self) -> S { self.width.clone() }
@@ -39,26 +62,14 @@ impl<P, S> Subdivide for Ncube<P, S>
           S: BaseFloat + PartialOrd + NumCast,
 {
     fn subdivide(&self) -> Vec<Ncube<P, S>> {
-        let _2 = NumCast::from(2.0f64).unwrap();
-        let dimension = Dimension::dimension(None::<P>);
-        let new_width = self.width / _2;
-        (0..2.pow(dimension as u32))
-            .map(|n: i32| {
-                let mut new_center = self.center;
-                let dx = new_width / _2;
-                for i in 0..dimension {
-                    new_center[i] = new_center[i] + match n / 2.pow(i as u32) % 2 {
-                        0 => -dx,
-                        1 => dx,
-                        _ => unreachable!(),
-                    };
-                }
-                Ncube {
-                    center: new_center,
-                    width: new_width,
-                }
-            })
-        .collect()
+        let _2: S = NumCast::from(2.0f64).unwrap();
+        // Equal extents stay equal under subdivision, so each orthotope child
+        // maps back onto a cube whose width is twice its (shared) half-extent.
+        self.orthotope()
+            .subdivide()
+            .into_iter()
+            .map(|child| Ncube { center: child.center(), width: child.half_extents()[0] * _2 })
+            .collect()
     }
 }
 
@@ -67,22 +78,15 @@ impl<P, S> Partition<P> for Ncube<P, S>
           S: BaseFloat + PartialOrd + NumCast,
 {
     fn contains(&self, elem: &P) -> bool {
-        let _2 = NumCast::from(2.0f64).unwrap();
-        (0..Dimension::dimension(None::<P>))
-            .all(|i| {
-                let off = (self.center[i] - elem[i]) * _2;
-                (-self.width <= off) && (off < self.width)
-            })
+        self.orthotope().contains(elem)
     }
 
     fn dispatch(&self, elem: &P) -> usize {
-        (0..Dimension::dimension(None::<P>))
-            .map(|k| if elem[k] < self.center[k] {0} else {1 << k})
-            .fold(0, |a, b| a + b)
+        self.orthotope().dispatch(elem)
     }
 }
 
-#[cfg(any(test, feature = "arbitrary"))]
+#[cfg(all(feature = "std", any(test, feature = "arbitrary")))]
 impl<P: Arbitrary, S: PartialOrd + Zero + Arbitrary> Arbitrary for Ncube<P, S> {
     fn arbitrary<G: Gen>(g: &mut G) -> Ncube<P, S> {
         use std::iter::repeat;
@@ -0,0 +1,144 @@
+//! Axis-aligned orthotope (box) partitioning scheme
+
+use core::ops::{Index, IndexMut};
+use num::{PrimInt, NumCast};
+use core::cmp::PartialOrd;
+use alloc::vec::Vec;
+use nalgebra::{Dimension, BaseFloat, zero};
+#[cfg(all(feature = "std", any(test, feature = "arbitrary")))]
+use quickcheck::{Arbitrary, Gen};
+use partition::{Partition, Subdivide};
+
+
+/// An axis-aligned orthotope partitioning scheme
+///
+/// Unlike [`Ncube`](struct.Ncube.html), which forces cubic cells through a
+/// single scalar width, an `Northotope` carries a separate half-extent per
+/// axis. On domains whose extent differs between axes this avoids spending
+/// tree depth halving dimensions that are already tight.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Northotope<P, S> {
+    center: P,
+    half_extents: P,
+    _marker: ::core::marker::PhantomData<S>,
+}
+
+impl<P, S> Northotope<P, S>
+    where P: Dimension + Index<usize, Output=S> + IndexMut<usize, Output=S> + Copy,
+          S: BaseFloat + PartialOrd + NumCast,
+{
+    /// Create a new orthotope given its center and per-axis half-extents
+    pub fn new(center: P, half_extents: P) -> Northotope<P, S> {
+        for i in 0..Dimension::dimension(None::<P>) {
+            assert!(half_extents[i] > zero());
+        }
+        Northotope { center: center, half_extents: half_extents, _marker: ::core::marker::PhantomData }
+    }
+
+    /// The center of the orthotope
+    pub fn center(&self) -> P { self.center }
+
+    /// The per-axis half-extents of the orthotope
+    pub fn half_extents(&self) -> P { self.half_extents }
+
+    /// Build a tight orthotope bounding the given point set
+    ///
+    /// Panics on an empty slice, mirroring the precondition style of
+    /// [`new`](#method.new). Degenerate axes (all points equal) are given a
+    /// positive half-extent so the result is always a valid partition.
+    pub fn bounding(points: &[P]) -> Northotope<P, S> {
+        assert!(!points.is_empty());
+        let first = points[0];
+        let dimension = Dimension::dimension(None::<P>);
+        let mut lower = first;
+        let mut upper = first;
+        for p in &points[1..] {
+            for i in 0..dimension {
+                if p[i] < lower[i] { lower[i] = p[i]; }
+                if p[i] > upper[i] { upper[i] = p[i]; }
+            }
+        }
+        let _2: S = NumCast::from(2.0f64).unwrap();
+        let mut center = first;
+        let mut half_extents = first;
+        for i in 0..dimension {
+            center[i] = (lower[i] + upper[i]) / _2;
+            let h = (upper[i] - lower[i]) / _2;
+            half_extents[i] = if h > zero() { h } else { NumCast::from(1.0f64).unwrap() };
+        }
+        Northotope { center: center, half_extents: half_extents, _marker: ::core::marker::PhantomData }
+    }
+}
+
+impl<P, S> Subdivide for Northotope<P, S>
+    where P: Dimension + Index<usize, Output=S> + IndexMut<usize, Output=S> + Copy,
+          S: BaseFloat + PartialOrd + NumCast,
+{
+    fn subdivide(&self) -> Vec<Northotope<P, S>> {
+        let _2: S = NumCast::from(2.0f64).unwrap();
+        let dimension = Dimension::dimension(None::<P>);
+        let mut new_half = self.half_extents;
+        for i in 0..dimension {
+            new_half[i] = new_half[i] / _2;
+        }
+        (0..2.pow(dimension as u32))
+            .map(|n: i32| {
+                let mut new_center = self.center;
+                for i in 0..dimension {
+                    new_center[i] = new_center[i] + match n / 2.pow(i as u32) % 2 {
+                        0 => -new_half[i],
+                        1 => new_half[i],
+                        _ => unreachable!(),
+                    };
+                }
+                Northotope { center: new_center, half_extents: new_half, _marker: ::core::marker::PhantomData }
+            })
+        .collect()
+    }
+}
+
+impl<P, S> Partition<P> for Northotope<P, S>
+    where P: Dimension + Index<usize, Output=S> + IndexMut<usize, Output=S> + Copy,
+          S: BaseFloat + PartialOrd + NumCast,
+{
+    fn contains(&self, elem: &P) -> bool {
+        (0..Dimension::dimension(None::<P>))
+            .all(|i| {
+                let off = self.center[i] - elem[i];
+                (-self.half_extents[i] <= off) && (off < self.half_extents[i])
+            })
+    }
+
+    fn dispatch(&self, elem: &P) -> usize {
+        (0..Dimension::dimension(None::<P>))
+            .map(|k| if elem[k] < self.center[k] {0} else {1 << k})
+            .fold(0, |a, b| a + b)
+    }
+}
+
+#[cfg(all(feature = "std", any(test, feature = "arbitrary")))]
+impl<P, S> Arbitrary for Northotope<P, S>
+    where P: Dimension + Index<usize, Output=S> + IndexMut<usize, Output=S> + Copy + Arbitrary,
+          S: BaseFloat + PartialOrd + NumCast + Arbitrary,
+{
+    fn arbitrary<G: Gen>(g: &mut G) -> Northotope<P, S> {
+        use std::iter::repeat;
+        let center = Arbitrary::arbitrary(g);
+        let half_extents = repeat(())
+            .map(|_| Arbitrary::arbitrary(g))
+            .filter(|h: &P| (0..Dimension::dimension(None::<P>)).all(|i| h[i] > zero()))
+            .next()
+            .unwrap();
+        Northotope::new(center, half_extents)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    pub use nalgebra::Point2;
+    pub use super::*;
+
+    partition_quickcheck!(northotope_pnt2_f32_partition, Northotope<Point2<f32>, f32>, Point2<f32>);
+}
@@ -0,0 +1,278 @@
+//! Out-of-core node storage as fixed-size, index-addressed blocks
+//!
+//! For point sets larger than RAM, tree nodes are serialized into fixed-size
+//! blocks addressed by a `u32` index and streamed through a [`BlockIO`]
+//! backend rather than held in memory all at once — mirroring the block-reader
+//! abstraction disc-image libraries use to serve data that never fully
+//! resides in memory. The in-memory [`MemoryStore`] is the default backend,
+//! with [`FileStore`] for on-disk datasets and [`LruStore`] layering a bounded
+//! write-through cache on top of any backend.
+//!
+//! This module provides the storage layer only: tree construction and
+//! traversal over `Ncube` do not use these stores, and do not request child
+//! nodes by block index or spill completed subtrees to a store.
+//!
+//! This module needs `std` (file I/O and collections) and is therefore gated
+//! behind the `std` feature.
+#![cfg(feature = "std")]
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+/// Storage backend serving and persisting tree nodes as fixed-size blocks.
+pub trait BlockIO {
+    /// The fixed block size, in bytes, of this store.
+    fn block_size(&self) -> usize;
+
+    /// Read the block at `idx` into `out`.
+    ///
+    /// `out` must be exactly [`block_size`](#tymethod.block_size) bytes long.
+    fn read_block(&mut self, idx: u32, out: &mut [u8]) -> io::Result<()>;
+
+    /// Write `data` as the block at `idx`.
+    ///
+    /// `data` must be exactly [`block_size`](#tymethod.block_size) bytes long.
+    fn write_block(&mut self, idx: u32, data: &[u8]) -> io::Result<()>;
+}
+
+fn check_len(kind: &str, got: usize, want: usize) -> io::Result<()> {
+    if got != want {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} buffer is {} bytes, expected block size {}", kind, got, want),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// In-memory block store, the default backend.
+pub struct MemoryStore {
+    block_size: usize,
+    blocks: HashMap<u32, Box<[u8]>>,
+}
+
+impl MemoryStore {
+    /// Create an empty in-memory store with the given block size.
+    pub fn new(block_size: usize) -> MemoryStore {
+        MemoryStore { block_size: block_size, blocks: HashMap::new() }
+    }
+}
+
+impl BlockIO for MemoryStore {
+    fn block_size(&self) -> usize { self.block_size }
+
+    fn read_block(&mut self, idx: u32, out: &mut [u8]) -> io::Result<()> {
+        check_len("read", out.len(), self.block_size)?;
+        match self.blocks.get(&idx) {
+            Some(block) => { out.copy_from_slice(block); Ok(()) }
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("block {} not present", idx),
+            )),
+        }
+    }
+
+    fn write_block(&mut self, idx: u32, data: &[u8]) -> io::Result<()> {
+        check_len("write", data.len(), self.block_size)?;
+        self.blocks.insert(idx, data.to_vec().into_boxed_slice());
+        Ok(())
+    }
+}
+
+/// File-backed block store placing block `idx` at byte offset `idx * block_size`.
+pub struct FileStore {
+    block_size: usize,
+    file: File,
+}
+
+impl FileStore {
+    /// Create a file-backed store over an already opened `file` with the given
+    /// block size.
+    pub fn new(file: File, block_size: usize) -> FileStore {
+        FileStore { block_size: block_size, file: file }
+    }
+
+    fn offset(&self, idx: u32) -> u64 {
+        idx as u64 * self.block_size as u64
+    }
+}
+
+impl BlockIO for FileStore {
+    fn block_size(&self) -> usize { self.block_size }
+
+    fn read_block(&mut self, idx: u32, out: &mut [u8]) -> io::Result<()> {
+        check_len("read", out.len(), self.block_size)?;
+        self.file.seek(SeekFrom::Start(self.offset(idx)))?;
+        self.file.read_exact(out)
+    }
+
+    fn write_block(&mut self, idx: u32, data: &[u8]) -> io::Result<()> {
+        check_len("write", data.len(), self.block_size)?;
+        self.file.seek(SeekFrom::Start(self.offset(idx)))?;
+        self.file.write_all(data)
+    }
+}
+
+/// A bounded write-through LRU cache layered over any [`BlockIO`] backend.
+///
+/// Reads are served from the cache when warm and otherwise fetched from the
+/// backend and inserted; writes update both the cache and the backend. When
+/// the cache is full the least-recently-used block is evicted.
+///
+/// Access recency is tracked with a monotonic tick counter and an ordered map
+/// from tick to block index, so touching and evicting are `O(log n)` rather
+/// than scanning the whole order on every access.
+pub struct LruStore<S> {
+    inner: S,
+    capacity: usize,
+    cache: HashMap<u32, Box<[u8]>>,
+    ticks: HashMap<u32, u64>,
+    order: BTreeMap<u64, u32>,
+    clock: u64,
+}
+
+impl<S: BlockIO> LruStore<S> {
+    /// Wrap `inner` with a cache holding at most `capacity` blocks.
+    pub fn new(inner: S, capacity: usize) -> LruStore<S> {
+        assert!(capacity > 0);
+        LruStore {
+            inner: inner,
+            capacity: capacity,
+            cache: HashMap::new(),
+            ticks: HashMap::new(),
+            order: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// Consume the cache and return the underlying backend.
+    pub fn into_inner(self) -> S { self.inner }
+
+    fn touch(&mut self, idx: u32) {
+        if let Some(old) = self.ticks.remove(&idx) {
+            self.order.remove(&old);
+        }
+        self.clock += 1;
+        self.ticks.insert(idx, self.clock);
+        self.order.insert(self.clock, idx);
+    }
+
+    fn insert(&mut self, idx: u32, block: Box<[u8]>) {
+        if !self.cache.contains_key(&idx) && self.cache.len() >= self.capacity {
+            if let Some((&tick, &evict)) = self.order.iter().next() {
+                self.order.remove(&tick);
+                self.ticks.remove(&evict);
+                self.cache.remove(&evict);
+            }
+        }
+        self.cache.insert(idx, block);
+        self.touch(idx);
+    }
+}
+
+impl<S: BlockIO> BlockIO for LruStore<S> {
+    fn block_size(&self) -> usize { self.inner.block_size() }
+
+    fn read_block(&mut self, idx: u32, out: &mut [u8]) -> io::Result<()> {
+        check_len("read", out.len(), self.block_size())?;
+        if let Some(block) = self.cache.get(&idx) {
+            out.copy_from_slice(block);
+            self.touch(idx);
+            return Ok(());
+        }
+        self.inner.read_block(idx, out)?;
+        self.insert(idx, out.to_vec().into_boxed_slice());
+        Ok(())
+    }
+
+    fn write_block(&mut self, idx: u32, data: &[u8]) -> io::Result<()> {
+        check_len("write", data.len(), self.block_size())?;
+        self.inner.write_block(idx, data)?;
+        self.insert(idx, data.to_vec().into_boxed_slice());
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::OpenOptions;
+
+    /// Backend that never serves a read, so a cache hit is the only way a read
+    /// can succeed — lets tests observe which blocks remain cached.
+    struct NoRead { block_size: usize }
+
+    impl BlockIO for NoRead {
+        fn block_size(&self) -> usize { self.block_size }
+        fn read_block(&mut self, idx: u32, _out: &mut [u8]) -> io::Result<()> {
+            Err(io::Error::new(io::ErrorKind::NotFound, format!("no read of {}", idx)))
+        }
+        fn write_block(&mut self, _idx: u32, _data: &[u8]) -> io::Result<()> { Ok(()) }
+    }
+
+    #[test]
+    fn memory_round_trip() {
+        let mut store = MemoryStore::new(4);
+        store.write_block(2, &[1, 2, 3, 4]).unwrap();
+        let mut out = [0u8; 4];
+        store.read_block(2, &mut out).unwrap();
+        assert_eq!(out, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let path = std::env::temp_dir().join("acacia_filestore_round_trip.bin");
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(true)
+            .open(&path).unwrap();
+        let mut store = FileStore::new(file, 4);
+        store.write_block(0, &[9, 8, 7, 6]).unwrap();
+        store.write_block(3, &[1, 2, 3, 4]).unwrap();
+        let mut out = [0u8; 4];
+        store.read_block(3, &mut out).unwrap();
+        assert_eq!(out, [1, 2, 3, 4]);
+        store.read_block(0, &mut out).unwrap();
+        assert_eq!(out, [9, 8, 7, 6]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn mis_sized_buffer_is_invalid_input() {
+        let mut store = MemoryStore::new(8);
+        let mut small = [0u8; 4];
+        let err = store.read_block(0, &mut small).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let err = store.write_block(0, &[0u8; 4]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn evicts_least_recently_used_not_oldest_inserted() {
+        let mut store = LruStore::new(NoRead { block_size: 4 }, 2);
+        store.write_block(0, &[0, 0, 0, 0]).unwrap();
+        store.write_block(1, &[1, 1, 1, 1]).unwrap();
+        // Touch 0 so 1 becomes the least recently used.
+        let mut out = [0u8; 4];
+        store.read_block(0, &mut out).unwrap();
+        // Insert 2 — must evict 1 (LRU), not 0 (oldest inserted).
+        store.write_block(2, &[2, 2, 2, 2]).unwrap();
+        assert!(store.read_block(0, &mut out).is_ok(), "0 should still be cached");
+        assert!(store.read_block(2, &mut out).is_ok(), "2 should be cached");
+        assert!(store.read_block(1, &mut out).is_err(), "1 should have been evicted");
+    }
+
+    #[test]
+    fn write_through_reaches_backend() {
+        let mut store = LruStore::new(MemoryStore::new(4), 1);
+        store.write_block(5, &[4, 3, 2, 1]).unwrap();
+        // Evict 5 from the cache by inserting another block.
+        store.write_block(6, &[0, 0, 0, 0]).unwrap();
+        // 5 is gone from the cache but must still be readable from the backend.
+        let mut out = [0u8; 4];
+        store.read_block(5, &mut out).unwrap();
+        assert_eq!(out, [4, 3, 2, 1]);
+    }
+}